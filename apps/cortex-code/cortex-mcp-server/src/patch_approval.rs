@@ -1,11 +1,15 @@
 use std::collections::HashMap;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use cortex_core::CortexConversation;
 use cortex_core::protocol::FileChange;
 use cortex_core::protocol::Op;
 use cortex_core::protocol::ReviewDecision;
+use futures::StreamExt as _;
+use futures::stream::FuturesUnordered;
 use mcp_types::ElicitRequest;
 use mcp_types::ElicitRequestParamsRequestedSchema;
 use mcp_types::JSONRPCErrorError;
@@ -14,10 +18,36 @@ use mcp_types::RequestId;
 use serde::Deserialize;
 use serde::Serialize;
 use serde_json::json;
+use tokio::fs;
 use tracing::error;
+use tracing::warn;
 
 use crate::cortex_tool_runner::INVALID_PARAMS_ERROR_CODE;
 use crate::outgoing_message::OutgoingMessageSender;
+use crate::patch_audit::AuditRecord;
+use crate::patch_audit::PatchAuditStore;
+use crate::patch_audit::ResolutionKind;
+use crate::patch_audit::changes_digest;
+use crate::patch_reviewers::PendingReviewer;
+use crate::patch_reviewers::ReviewerId;
+use crate::patch_reviewers::ReviewerPolicy;
+use crate::patch_reviewers::ReviewerTally;
+use crate::patch_reviewers::TallyOutcome;
+use crate::patch_session::PatchSessionRegistry;
+use crate::patch_verification::PatchVerification;
+use crate::patch_verification::verify_patch;
+
+/// How long we wait for a reviewer quorum to be reached before denying the
+/// patch outright.
+const REVIEWER_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Current unix timestamp, for audit record timestamps.
+fn unix_now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
 
 #[derive(Debug, Serialize)]
 pub struct PatchApprovalElicitRequestParams {
@@ -33,11 +63,186 @@ pub struct PatchApprovalElicitRequestParams {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cortex_grant_root: Option<PathBuf>,
     pub cortex_changes: HashMap<PathBuf, FileChange>,
+    /// Result of pre-flight running the configured verification command
+    /// (e.g. `cargo test`) against the proposed changes in an ephemeral
+    /// worktree, so the reviewer can approve with evidence the patch
+    /// builds. `None` when no verification command is configured.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cortex_verification: Option<PatchVerification>,
+    /// Reviewers that have not yet responded, so a client can render
+    /// pending-reviewer state when a patch is routed to more than one.
+    pub cortex_reviewers: Vec<PendingReviewer>,
+    /// Correlates this approval round with a [`PatchSession`](crate::patch_session::PatchSession),
+    /// so a reviewer's MCP client can submit `TextChange` edits against the
+    /// proposed contents and have them merged before approval resolves.
+    pub cortex_patch_session_id: String,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct PatchApprovalResponse {
-    pub decision: ReviewDecision,
+    /// Overall decision. Used as-is when `path_decisions` is absent or
+    /// empty, and as the fallback for any path the reviewer didn't give a
+    /// per-path decision for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decision: Option<ReviewDecision>,
+    /// Per-path decisions, letting a reviewer approve some files, reject
+    /// others, and request changes on the rest within one elicitation
+    /// round. Keyed by the same paths as `cortex_changes`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path_decisions: Option<HashMap<PathBuf, ReviewDecision>>,
+}
+
+/// Builds the `requestedSchema` for the elicitation: one enum property per
+/// proposed path (so an MCP client can render a per-file checkbox/select),
+/// plus the overall fallback `decision`.
+fn requested_schema_for(changes: &HashMap<PathBuf, FileChange>) -> ElicitRequestParamsRequestedSchema {
+    let decision_values = json!(["approved", "approved_for_session", "denied", "abort"]);
+    let mut properties = serde_json::Map::new();
+    properties.insert(
+        "decision".to_string(),
+        json!({ "type": "string", "enum": decision_values }),
+    );
+
+    let mut path_properties = serde_json::Map::new();
+    for path in changes.keys() {
+        path_properties.insert(
+            path.to_string_lossy().into_owned(),
+            json!({ "type": "string", "enum": decision_values }),
+        );
+    }
+    properties.insert(
+        "path_decisions".to_string(),
+        json!({ "type": "object", "properties": path_properties }),
+    );
+
+    ElicitRequestParamsRequestedSchema {
+        r#type: "object".to_string(),
+        properties: serde_json::Value::Object(properties),
+        required: None,
+    }
+}
+
+/// Resolves the reviewer's response into the single decision to submit via
+/// `Op::PatchApproval`, plus the per-path split of that decision.
+///
+/// `Op::PatchApproval` only carries one decision for the whole patch, so a
+/// partial approval can't be expressed through it alone; when some paths
+/// are accepted and others aren't, the overall decision submitted is
+/// `Denied` (so the agent is told the patch as a whole needs revision), and
+/// the caller is responsible for applying `accepted` directly instead of
+/// relying on `Op::PatchApproval` to have applied them.
+fn resolve_decision(
+    changes: &HashMap<PathBuf, FileChange>,
+    response: &PatchApprovalResponse,
+) -> (ReviewDecision, Vec<PathBuf>, Vec<PathBuf>) {
+    let Some(path_decisions) = response.path_decisions.as_ref().filter(|d| !d.is_empty()) else {
+        let decision = response.decision.clone().unwrap_or(ReviewDecision::Denied);
+        let accepted = if matches!(decision, ReviewDecision::Approved | ReviewDecision::ApprovedForSession) {
+            changes.keys().cloned().collect()
+        } else {
+            Vec::new()
+        };
+        return (decision, accepted, Vec::new());
+    };
+
+    let fallback = response.decision.clone().unwrap_or(ReviewDecision::Denied);
+    let (accepted, rejected): (Vec<PathBuf>, Vec<PathBuf>) = changes.keys().cloned().partition(|path| {
+        matches!(
+            path_decisions.get(path).unwrap_or(&fallback),
+            ReviewDecision::Approved | ReviewDecision::ApprovedForSession
+        )
+    });
+
+    let decision = if rejected.is_empty() {
+        ReviewDecision::Approved
+    } else {
+        ReviewDecision::Denied
+    };
+    (decision, accepted, rejected)
+}
+
+/// Writes `accepted` paths' `changes` directly into `grant_root`, bypassing
+/// `Op::PatchApproval` for a partially-approved patch. The overall decision
+/// submitted for such a patch is `Denied` (see [`resolve_decision`]), which
+/// would otherwise mean none of the reviewer's approved paths ever land on
+/// disk.
+async fn apply_accepted_changes(grant_root: &Path, changes: &HashMap<PathBuf, FileChange>, accepted: &[PathBuf]) {
+    for path in accepted {
+        let Some(change) = changes.get(path) else {
+            continue;
+        };
+        let result = match change {
+            FileChange::Add { content } => write_change_file(grant_root, path, content).await,
+            FileChange::Update { new_content, move_path, .. } => {
+                write_change_file(grant_root, move_path.as_deref().unwrap_or(path), new_content).await
+            }
+            FileChange::Delete => delete_change_file(grant_root, path).await,
+        };
+        if let Err(err) = result {
+            error!("failed to apply accepted path {path:?} directly to {grant_root:?}: {err}");
+        }
+    }
+}
+
+async fn write_change_file(grant_root: &Path, path: &Path, content: &str) -> std::io::Result<()> {
+    let dest = grant_root.join(path);
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    fs::write(dest, content).await
+}
+
+async fn delete_change_file(grant_root: &Path, path: &Path) -> std::io::Result<()> {
+    let dest = grant_root.join(path);
+    if fs::try_exists(&dest).await.unwrap_or(false) {
+        fs::remove_file(dest).await?;
+    }
+    Ok(())
+}
+
+/// Returns the editable proposed content for a change, i.e. the text a
+/// reviewer's `TextChange` edits apply against. `Delete` has no content to
+/// edit.
+fn editable_content(change: &FileChange) -> Option<&str> {
+    match change {
+        FileChange::Add { content } => Some(content),
+        FileChange::Update { new_content, .. } => Some(new_content),
+        FileChange::Delete => None,
+    }
+}
+
+/// Writes the reviewer-merged content produced by a patch session directly
+/// into `grant_root`, overwriting whatever `Op::PatchApproval` applied.
+///
+/// `Op::PatchApproval` only carries a decision, not replacement content, so
+/// there's no way to hand the core conversation the edited text instead of
+/// the agent's original `FileChange`s; writing it to disk ourselves, to the
+/// same destination the original change would have used, is the only way
+/// collaborative edits actually reach the approved files.
+async fn apply_merged_content(
+    grant_root: &Path,
+    changes: &HashMap<PathBuf, FileChange>,
+    merged: HashMap<PathBuf, String>,
+) {
+    for (path, content) in merged {
+        let dest = match changes.get(&path) {
+            Some(FileChange::Update {
+                move_path: Some(move_path),
+                ..
+            }) => move_path.clone(),
+            _ => path.clone(),
+        };
+        let dest = grant_root.join(dest);
+        if let Some(parent) = dest.parent() {
+            if let Err(err) = fs::create_dir_all(parent).await {
+                error!("failed to create parent dir for merged patch content at {dest:?}: {err}");
+                continue;
+            }
+        }
+        if let Err(err) = fs::write(&dest, content).await {
+            error!("failed to write merged patch content to {dest:?}: {err}");
+        }
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -47,31 +252,100 @@ pub(crate) async fn handle_patch_approval_request(
     grant_root: Option<PathBuf>,
     changes: HashMap<PathBuf, FileChange>,
     outgoing: Arc<OutgoingMessageSender>,
+    reviewers: Vec<(ReviewerId, Arc<OutgoingMessageSender>)>,
+    policy: ReviewerPolicy,
+    patch_sessions: Arc<PatchSessionRegistry>,
+    audit: Arc<PatchAuditStore>,
+    conversation_id: String,
     cortex: Arc<CortexConversation>,
     request_id: RequestId,
     tool_call_id: String,
     event_id: String,
+    verification_command: Vec<String>,
 ) {
+    let digest = changes_digest(&changes);
+    if let Some(decision) = audit.remembered_decision(&conversation_id, &digest).await {
+        warn!(
+            "event {event_id} matches a previously-decided change set (digest {digest}); auto-applying remembered decision"
+        );
+        if let Err(submit_err) = cortex.submit(Op::PatchApproval { id: event_id, decision }).await {
+            error!("failed to submit remembered PatchApproval: {submit_err}");
+        }
+        return;
+    }
+
     let mut message_lines = Vec::new();
     if let Some(r) = &reason {
         message_lines.push(r.clone());
     }
     message_lines.push("Allow Cortex to apply proposed code changes?".to_string());
 
+    let verification = verify_patch(&event_id, grant_root.as_deref(), &changes, &verification_command).await;
+    if let Some(v) = &verification {
+        if v.timed_out {
+            error!("verification for event {event_id} timed out; denying patch");
+            audit
+                .record(AuditRecord {
+                    event_id: event_id.clone(),
+                    conversation_id,
+                    changes_digest: digest,
+                    reason: reason.clone(),
+                    decision: ReviewDecision::Denied,
+                    responder: None,
+                    resolution: ResolutionKind::Timeout,
+                    recorded_at_unix: unix_now(),
+                })
+                .await;
+            if let Err(submit_err) = cortex
+                .submit(Op::PatchApproval {
+                    id: event_id,
+                    decision: ReviewDecision::Denied,
+                })
+                .await
+            {
+                error!("failed to submit denied PatchApproval after verification timeout: {submit_err}");
+            }
+            return;
+        }
+        message_lines.push(if v.passed {
+            format!("Verification (`{}`) passed.", v.command)
+        } else {
+            format!("Verification (`{}`) failed or could not run.", v.command)
+        });
+    }
+
+    // One patch session per approval round, reusing `event_id` so the two
+    // stay correlated without minting a second identifier.
+    let patch_session_id = event_id.clone();
+    let initial_content: HashMap<PathBuf, String> = changes
+        .iter()
+        .filter_map(|(path, change)| editable_content(change).map(|content| (path.clone(), content.to_string())))
+        .collect();
+    let patch_session = patch_sessions.create(patch_session_id.clone(), initial_content);
+    for (_, sender) in &reviewers {
+        patch_session.attach(sender.clone());
+    }
+
+    let reason_for_audit = reason.clone();
+    let grant_root_for_merge = grant_root.clone();
+    let requested_schema = requested_schema_for(&changes);
+    let reviewer_ids: Vec<ReviewerId> = reviewers.iter().map(|(id, _)| id.clone()).collect();
     let params = PatchApprovalElicitRequestParams {
         message: message_lines.join("\n"),
-        requested_schema: ElicitRequestParamsRequestedSchema {
-            r#type: "object".to_string(),
-            properties: json!({}),
-            required: None,
-        },
+        requested_schema,
         cortex_elicitation: "patch-approval".to_string(),
         cortex_mcp_tool_call_id: tool_call_id.clone(),
         cortex_event_id: event_id.clone(),
         cortex_call_id: call_id,
         cortex_reason: reason,
         cortex_grant_root: grant_root,
-        cortex_changes: changes,
+        cortex_changes: changes.clone(),
+        cortex_verification: verification,
+        cortex_reviewers: reviewer_ids
+            .iter()
+            .map(|id| PendingReviewer { id: id.clone() })
+            .collect(),
+        cortex_patch_session_id: patch_session_id.clone(),
     };
     let params_json = match serde_json::to_value(&params) {
         Ok(value) => value,
@@ -94,57 +368,315 @@ pub(crate) async fn handle_patch_approval_request(
         }
     };
 
-    let on_response = outgoing
-        .send_request(ElicitRequest::METHOD, Some(params_json))
-        .await;
-
-    // Listen for the response on a separate task so we don't block the main agent loop.
+    // Fan the elicitation out to every registered reviewer and collect the
+    // responses on a separate task so we don't block the main agent loop.
     {
         let cortex = cortex.clone();
         let event_id = event_id.clone();
+        let reason = reason_for_audit;
         tokio::spawn(async move {
-            on_patch_approval_response(event_id, on_response, cortex).await;
+            on_patch_approval_responses(
+                event_id,
+                changes,
+                reviewers,
+                policy,
+                params_json,
+                patch_sessions,
+                patch_session_id,
+                audit,
+                conversation_id,
+                digest,
+                reason,
+                cortex,
+                grant_root_for_merge,
+            )
+            .await;
         });
     }
 }
 
-pub(crate) async fn on_patch_approval_response(
+/// Resolves a single reviewer's raw MCP response into the decision they
+/// cast plus the paths they accepted, applying the per-path resolution
+/// from [`resolve_decision`]. The returned `bool` is `true` when the
+/// response failed to deserialize and the `Denied` fallback was used
+/// instead, for the audit trail.
+fn decode_reviewer_response(
+    changes: &HashMap<PathBuf, FileChange>,
+    response: mcp_types::Result,
+) -> (ReviewDecision, Vec<PathBuf>, bool) {
+    let mut deserialization_failed = false;
+    let response = serde_json::from_value::<PatchApprovalResponse>(response).unwrap_or_else(|err| {
+        error!("failed to deserialize PatchApprovalResponse: {err}");
+        deserialization_failed = true;
+        PatchApprovalResponse {
+            decision: Some(ReviewDecision::Denied),
+            path_decisions: None,
+        }
+    });
+    let (decision, accepted_paths, rejected_paths) = resolve_decision(changes, &response);
+    if !rejected_paths.is_empty() {
+        warn!("reviewer rejected paths: {rejected_paths:?}; agent should revise them");
+    }
+    (decision, accepted_paths, deserialization_failed)
+}
+
+/// Fans `params_json` out to every reviewer in `reviewers`, folds each
+/// response into a [`ReviewerTally`] evaluated against `policy`, and
+/// submits the resulting `Op::PatchApproval` as soon as the policy
+/// resolves (or once `REVIEWER_TIMEOUT` elapses, whichever is first).
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn on_patch_approval_responses(
     event_id: String,
-    receiver: tokio::sync::oneshot::Receiver<mcp_types::Result>,
+    changes: HashMap<PathBuf, FileChange>,
+    reviewers: Vec<(ReviewerId, Arc<OutgoingMessageSender>)>,
+    policy: ReviewerPolicy,
+    params_json: serde_json::Value,
+    patch_sessions: Arc<PatchSessionRegistry>,
+    patch_session_id: String,
+    audit: Arc<PatchAuditStore>,
+    conversation_id: String,
+    changes_digest: String,
+    reason: Option<String>,
     cortex: Arc<CortexConversation>,
+    grant_root: Option<PathBuf>,
 ) {
-    let response = receiver.await;
-    let value = match response {
-        Ok(value) => value,
-        Err(err) => {
-            error!("request failed: {err:?}");
-            if let Err(submit_err) = cortex
-                .submit(Op::PatchApproval {
-                    id: event_id.clone(),
-                    decision: ReviewDecision::Denied,
-                })
-                .await
-            {
-                error!("failed to submit denied PatchApproval after request failure: {submit_err}");
+    let reviewer_ids: Vec<ReviewerId> = reviewers.iter().map(|(id, _)| id.clone()).collect();
+    let mut tally = ReviewerTally::new(policy, reviewer_ids);
+
+    let mut pending = FuturesUnordered::new();
+    for (id, sender) in reviewers {
+        let params_json = params_json.clone();
+        pending.push(async move {
+            let receiver = sender.send_request(ElicitRequest::METHOD, Some(params_json)).await;
+            (id, receiver.await)
+        });
+    }
+
+    let deadline = tokio::time::sleep(REVIEWER_TIMEOUT);
+    tokio::pin!(deadline);
+
+    let mut resolution = ResolutionKind::UserAction;
+    let mut responder = None;
+    let mut accepted_paths = Vec::new();
+    let decision = loop {
+        tokio::select! {
+            biased;
+            _ = &mut deadline => {
+                error!(
+                    "timed out waiting for reviewers {:?} on event {event_id}; denying patch",
+                    tally.pending()
+                );
+                resolution = ResolutionKind::Timeout;
+                break ReviewDecision::Denied;
+            }
+            next = pending.next() => {
+                let Some((id, response)) = next else {
+                    // No reviewer response left to wait for. Every policy
+                    // resolves by the last pending reviewer's response, so
+                    // this is reached only when there were no reviewers.
+                    break ReviewDecision::Denied;
+                };
+                let (decision, response_accepted_paths) = match response {
+                    Ok(value) => {
+                        let (decision, accepted_paths, deserialization_failed) = decode_reviewer_response(&changes, value);
+                        if deserialization_failed {
+                            resolution = ResolutionKind::DeserializationFailure;
+                        }
+                        (decision, accepted_paths)
+                    }
+                    Err(err) => {
+                        error!("request to reviewer {id} failed: {err:?}");
+                        (ReviewDecision::Denied, Vec::new())
+                    }
+                };
+                if let TallyOutcome::Resolved(decision) = tally.record(id.clone(), decision) {
+                    responder = Some(id);
+                    accepted_paths = response_accepted_paths;
+                    break decision;
+                }
             }
-            return;
         }
     };
 
-    let response = serde_json::from_value::<PatchApprovalResponse>(value).unwrap_or_else(|err| {
-        error!("failed to deserialize PatchApprovalResponse: {err}");
-        PatchApprovalResponse {
-            decision: ReviewDecision::Denied,
+    // A `Denied` resolution can still carry paths the winning reviewer
+    // accepted (a partial approval); since `Op::PatchApproval` can't convey
+    // that split, apply those paths directly so reviewer approval of a
+    // subset isn't silently discarded just because the rest was rejected.
+    // This is safe to do before `submit`: core does nothing to these paths
+    // on a `Denied` decision, so there's no later write to race with.
+    if matches!(decision, ReviewDecision::Denied) && !accepted_paths.is_empty() {
+        match &grant_root {
+            Some(grant_root) => apply_accepted_changes(grant_root, &changes, &accepted_paths).await,
+            None => warn!(
+                "reviewer partially approved {accepted_paths:?} on event {event_id} but no grant root to apply them to"
+            ),
         }
-    });
+    }
+
+    // Collect merged session edits before the session is torn down, but
+    // don't write them yet: on an `Approved` decision, submitting
+    // `Op::PatchApproval` below is what makes core write the agent's
+    // original `FileChange`s to `grant_root`, so writing the merge first
+    // would just get clobbered the moment `submit` resolves.
+    let merged_content = patch_sessions
+        .get(&patch_session_id)
+        .map(|session| session.merged_content())
+        .filter(|merged| !merged.is_empty());
+    patch_sessions.remove(&patch_session_id);
 
-    if let Err(err) = cortex
-        .submit(Op::PatchApproval {
-            id: event_id,
-            decision: response.decision,
+    let approved = matches!(decision, ReviewDecision::Approved);
+
+    audit
+        .record(AuditRecord {
+            event_id: event_id.clone(),
+            conversation_id,
+            changes_digest,
+            reason,
+            decision: decision.clone(),
+            responder,
+            resolution,
+            recorded_at_unix: unix_now(),
         })
-        .await
-    {
+        .await;
+
+    if let Err(err) = cortex.submit(Op::PatchApproval { id: event_id, decision }).await {
         error!("failed to submit PatchApproval: {err}");
     }
+
+    // Now that core has (attempted to) apply the agent's original
+    // `FileChange`s, layer the reviewer's merged edits on top so they
+    // survive as the final content on disk.
+    if approved {
+        if let Some(merged) = merged_content {
+            match &grant_root {
+                Some(grant_root) => apply_merged_content(grant_root, &changes, merged).await,
+                None => warn!("patch session {patch_session_id} has merged edits but no grant root to apply them to"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(content: &str) -> FileChange {
+        FileChange::Add {
+            content: content.to_string(),
+        }
+    }
+
+    /// A throwaway directory under the system temp dir, removed and
+    /// recreated fresh for each test that touches the filesystem.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join("cortex-patch-approval-tests").join(name);
+            let _ = std::fs::remove_dir_all(&path);
+            std::fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+
+        fn path(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn response(decision: Option<ReviewDecision>, path_decisions: Option<HashMap<PathBuf, ReviewDecision>>) -> PatchApprovalResponse {
+        PatchApprovalResponse { decision, path_decisions }
+    }
+
+    #[test]
+    fn resolve_decision_falls_back_to_the_overall_decision_without_path_decisions() {
+        let changes = HashMap::from([(PathBuf::from("a.txt"), add("a")), (PathBuf::from("b.txt"), add("b"))]);
+        let (decision, accepted, rejected) = resolve_decision(&changes, &response(Some(ReviewDecision::Approved), None));
+        assert_eq!(decision, ReviewDecision::Approved);
+        assert_eq!(accepted.len(), 2);
+        assert!(rejected.is_empty());
+    }
+
+    #[test]
+    fn resolve_decision_defaults_to_denied_with_no_decision_at_all() {
+        let changes = HashMap::from([(PathBuf::from("a.txt"), add("a"))]);
+        let (decision, accepted, rejected) = resolve_decision(&changes, &response(None, None));
+        assert_eq!(decision, ReviewDecision::Denied);
+        assert!(accepted.is_empty());
+        assert!(rejected.is_empty());
+    }
+
+    #[test]
+    fn resolve_decision_approves_when_every_path_is_individually_approved() {
+        let changes = HashMap::from([(PathBuf::from("a.txt"), add("a")), (PathBuf::from("b.txt"), add("b"))]);
+        let path_decisions = HashMap::from([
+            (PathBuf::from("a.txt"), ReviewDecision::Approved),
+            (PathBuf::from("b.txt"), ReviewDecision::ApprovedForSession),
+        ]);
+        let (decision, accepted, rejected) = resolve_decision(&changes, &response(None, Some(path_decisions)));
+        assert_eq!(decision, ReviewDecision::Approved);
+        assert_eq!(accepted.len(), 2);
+        assert!(rejected.is_empty());
+    }
+
+    #[test]
+    fn resolve_decision_denies_overall_but_tracks_accepted_paths_on_a_partial_approval() {
+        let changes = HashMap::from([(PathBuf::from("a.txt"), add("a")), (PathBuf::from("b.txt"), add("b"))]);
+        let path_decisions = HashMap::from([(PathBuf::from("a.txt"), ReviewDecision::Approved)]);
+        let (decision, accepted, rejected) = resolve_decision(&changes, &response(Some(ReviewDecision::Denied), Some(path_decisions)));
+        assert_eq!(decision, ReviewDecision::Denied);
+        assert_eq!(accepted, vec![PathBuf::from("a.txt")]);
+        assert_eq!(rejected, vec![PathBuf::from("b.txt")]);
+    }
+
+    #[test]
+    fn resolve_decision_uses_the_overall_decision_as_fallback_for_unlisted_paths() {
+        let changes = HashMap::from([(PathBuf::from("a.txt"), add("a")), (PathBuf::from("b.txt"), add("b"))]);
+        let path_decisions = HashMap::from([(PathBuf::from("a.txt"), ReviewDecision::Approved)]);
+        let (decision, accepted, rejected) =
+            resolve_decision(&changes, &response(Some(ReviewDecision::Approved), Some(path_decisions)));
+        // "b.txt" has no explicit entry, so it falls back to the overall `Approved` decision.
+        assert_eq!(decision, ReviewDecision::Approved);
+        assert_eq!(accepted.len(), 2);
+        assert!(rejected.is_empty());
+    }
+
+    #[tokio::test]
+    async fn apply_accepted_changes_writes_add_and_skips_unaccepted_paths() {
+        let dir = TempDir::new("apply_accepted_changes_writes_add_and_skips_unaccepted_paths");
+        let changes = HashMap::from([
+            (PathBuf::from("accepted.txt"), add("accepted content")),
+            (PathBuf::from("rejected.txt"), add("rejected content")),
+        ]);
+        apply_accepted_changes(dir.path(), &changes, &[PathBuf::from("accepted.txt")]).await;
+
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("accepted.txt")).unwrap(),
+            "accepted content"
+        );
+        assert!(!dir.path().join("rejected.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn apply_accepted_changes_deletes_accepted_delete_paths() {
+        let dir = TempDir::new("apply_accepted_changes_deletes_accepted_delete_paths");
+        std::fs::write(dir.path().join("gone.txt"), "bye").unwrap();
+        let changes = HashMap::from([(PathBuf::from("gone.txt"), FileChange::Delete)]);
+        apply_accepted_changes(dir.path(), &changes, &[PathBuf::from("gone.txt")]).await;
+        assert!(!dir.path().join("gone.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn apply_merged_content_writes_merged_text_to_disk() {
+        let dir = TempDir::new("apply_merged_content_writes_merged_text_to_disk");
+        let changes = HashMap::from([(PathBuf::from("a.txt"), add("original"))]);
+        let merged = HashMap::from([(PathBuf::from("a.txt"), "merged by reviewer".to_string())]);
+        apply_merged_content(dir.path(), &changes, merged).await;
+        assert_eq!(std::fs::read_to_string(dir.path().join("a.txt")).unwrap(), "merged by reviewer");
+    }
 }
@@ -0,0 +1,387 @@
+use std::path::PathBuf;
+
+use cortex_core::protocol::FileChange;
+use cortex_core::protocol::ReviewDecision;
+use sha2::Digest;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::error;
+
+/// bb8 connection manager for a `rusqlite::Connection`, following the same
+/// pooling pattern used elsewhere for pooled database access. Every pooled
+/// connection opens the same on-disk database file.
+#[derive(Debug, Clone)]
+pub(crate) struct SqliteConnectionManager {
+    path: PathBuf,
+}
+
+impl SqliteConnectionManager {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+#[async_trait::async_trait]
+impl bb8::ManageConnection for SqliteConnectionManager {
+    type Connection = rusqlite::Connection;
+    type Error = rusqlite::Error;
+
+    async fn connect(&self) -> Result<Self::Connection, Self::Error> {
+        let path = self.path.clone();
+        tokio::task::spawn_blocking(move || rusqlite::Connection::open(path))
+            .await
+            .expect("sqlite connect task panicked")
+    }
+
+    async fn is_valid(&self, conn: &mut Self::Connection) -> Result<(), Self::Error> {
+        conn.execute_batch("SELECT 1")
+    }
+
+    fn has_broken(&self, _conn: &mut Self::Connection) -> bool {
+        false
+    }
+}
+
+/// How a recorded patch approval resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ResolutionKind {
+    UserAction,
+    Timeout,
+    DeserializationFailure,
+}
+
+impl ResolutionKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            ResolutionKind::UserAction => "user_action",
+            ResolutionKind::Timeout => "timeout",
+            ResolutionKind::DeserializationFailure => "deserialization_failure",
+        }
+    }
+}
+
+fn decision_as_str(decision: &ReviewDecision) -> &'static str {
+    match decision {
+        ReviewDecision::Approved => "approved",
+        ReviewDecision::ApprovedForSession => "approved_for_session",
+        ReviewDecision::Denied => "denied",
+        ReviewDecision::Abort => "abort",
+    }
+}
+
+/// Content-addressed identity of a proposed change set: the same paths
+/// with the same contents hash to the same digest regardless of map
+/// iteration order, so an identical re-proposal can be recognized.
+pub(crate) fn changes_digest(changes: &HashMap<PathBuf, FileChange>) -> String {
+    let mut paths: Vec<&PathBuf> = changes.keys().collect();
+    paths.sort();
+
+    let mut hasher = Sha256::new();
+    for path in paths {
+        hasher.update(path.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        match &changes[path] {
+            FileChange::Add { content } => {
+                hasher.update(b"add");
+                hasher.update(content.as_bytes());
+            }
+            FileChange::Delete => hasher.update(b"delete"),
+            FileChange::Update { new_content, move_path, .. } => {
+                hasher.update(b"update");
+                hasher.update(new_content.as_bytes());
+                if let Some(move_path) = move_path {
+                    hasher.update(move_path.to_string_lossy().as_bytes());
+                }
+            }
+        }
+        hasher.update([0]);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// One recorded approval decision, persisted so a future identical
+/// proposal can be recognized and so the TUI can render prior decisions.
+#[derive(Debug, Clone)]
+pub(crate) struct AuditRecord {
+    pub event_id: String,
+    /// Identifies the Cortex conversation the patch was proposed in, so a
+    /// remembered decision can only be auto-applied within the same
+    /// conversation rather than leaking across unrelated ones that happen
+    /// to propose an identical change set.
+    pub conversation_id: String,
+    pub changes_digest: String,
+    pub reason: Option<String>,
+    pub decision: ReviewDecision,
+    pub responder: Option<String>,
+    pub resolution: ResolutionKind,
+    pub recorded_at_unix: i64,
+}
+
+/// Pooled sqlite store recording every patch approval decision.
+#[derive(Clone)]
+pub(crate) struct PatchAuditStore {
+    pool: bb8::Pool<SqliteConnectionManager>,
+}
+
+impl PatchAuditStore {
+    pub(crate) async fn open(path: &Path) -> anyhow::Result<Self> {
+        let pool = bb8::Pool::builder()
+            .max_size(4)
+            .build(SqliteConnectionManager::new(path.to_path_buf()))
+            .await
+            .map_err(|err| anyhow::anyhow!("failed to build patch audit sqlite pool: {err}"))?;
+
+        let conn = pool
+            .get()
+            .await
+            .map_err(|err| anyhow::anyhow!("failed to get pooled sqlite connection: {err}"))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS patch_decisions (
+                event_id TEXT NOT NULL,
+                conversation_id TEXT NOT NULL,
+                changes_digest TEXT NOT NULL,
+                reason TEXT,
+                decision TEXT NOT NULL,
+                responder TEXT,
+                resolution TEXT NOT NULL,
+                recorded_at_unix INTEGER NOT NULL
+            )",
+        )?;
+        drop(conn);
+
+        Ok(Self { pool })
+    }
+
+    /// Persists `record`. Logs and swallows pool/query errors rather than
+    /// propagating them, since a failure to audit shouldn't block the
+    /// already-resolved approval from being submitted.
+    pub(crate) async fn record(&self, record: AuditRecord) {
+        let conn = match self.pool.get().await {
+            Ok(conn) => conn,
+            Err(err) => {
+                error!("failed to get pooled sqlite connection for patch audit: {err}");
+                return;
+            }
+        };
+
+        let result = conn.execute(
+            "INSERT INTO patch_decisions
+                (event_id, conversation_id, changes_digest, reason, decision, responder, resolution, recorded_at_unix)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![
+                record.event_id,
+                record.conversation_id,
+                record.changes_digest,
+                record.reason,
+                decision_as_str(&record.decision),
+                record.responder,
+                record.resolution.as_str(),
+                record.recorded_at_unix,
+            ],
+        );
+        if let Err(err) = result {
+            error!("failed to persist patch audit record for {}: {err}", record.event_id);
+        }
+    }
+
+    /// Returns prior decisions recorded for an identical change set (same
+    /// paths and content, via [`changes_digest`]) within the same
+    /// `conversation_id`, most recent first, for a "previous decisions on
+    /// similar patches" panel. Scoped to the conversation so a decision
+    /// made in one conversation never auto-resolves an identical-looking
+    /// patch proposed in an unrelated one.
+    pub(crate) async fn decisions_for_digest(&self, conversation_id: &str, digest: &str) -> Vec<AuditRecord> {
+        let conn = match self.pool.get().await {
+            Ok(conn) => conn,
+            Err(err) => {
+                error!("failed to get pooled sqlite connection for patch audit query: {err}");
+                return Vec::new();
+            }
+        };
+
+        let query = conn.prepare(
+            "SELECT event_id, conversation_id, changes_digest, reason, decision, responder, resolution, recorded_at_unix
+             FROM patch_decisions WHERE conversation_id = ?1 AND changes_digest = ?2 ORDER BY recorded_at_unix DESC",
+        );
+        let mut stmt = match query {
+            Ok(stmt) => stmt,
+            Err(err) => {
+                error!("failed to prepare patch audit query: {err}");
+                return Vec::new();
+            }
+        };
+
+        let rows = stmt.query_map(rusqlite::params![conversation_id, digest], |row| {
+            Ok(AuditRecord {
+                event_id: row.get(0)?,
+                conversation_id: row.get(1)?,
+                changes_digest: row.get(2)?,
+                reason: row.get(3)?,
+                decision: match row.get::<_, String>(4)?.as_str() {
+                    "approved" => ReviewDecision::Approved,
+                    "approved_for_session" => ReviewDecision::ApprovedForSession,
+                    "abort" => ReviewDecision::Abort,
+                    _ => ReviewDecision::Denied,
+                },
+                responder: row.get(5)?,
+                resolution: match row.get::<_, String>(6)?.as_str() {
+                    "timeout" => ResolutionKind::Timeout,
+                    "deserialization_failure" => ResolutionKind::DeserializationFailure,
+                    _ => ResolutionKind::UserAction,
+                },
+                recorded_at_unix: row.get(7)?,
+            })
+        });
+
+        match rows {
+            Ok(rows) => rows.filter_map(Result::ok).collect(),
+            Err(err) => {
+                error!("failed to read patch audit rows: {err}");
+                Vec::new()
+            }
+        }
+    }
+
+    /// Convenience wrapper used to decide whether an identical, already
+    /// re-proposed change set can be auto-resolved from a remembered
+    /// decision instead of eliciting the reviewer again. Scoped to
+    /// `conversation_id`, see [`Self::decisions_for_digest`].
+    pub(crate) async fn remembered_decision(&self, conversation_id: &str, digest: &str) -> Option<ReviewDecision> {
+        self.decisions_for_digest(conversation_id, digest)
+            .await
+            .into_iter()
+            .find(|record| record.resolution == ResolutionKind::UserAction)
+            .map(|record| record.decision)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(content: &str) -> FileChange {
+        FileChange::Add {
+            content: content.to_string(),
+        }
+    }
+
+    fn record(event_id: &str, conversation_id: &str, digest: &str, decision: ReviewDecision, resolution: ResolutionKind) -> AuditRecord {
+        AuditRecord {
+            event_id: event_id.to_string(),
+            conversation_id: conversation_id.to_string(),
+            changes_digest: digest.to_string(),
+            reason: None,
+            decision,
+            responder: Some("reviewer-1".to_string()),
+            resolution,
+            recorded_at_unix: 0,
+        }
+    }
+
+    #[test]
+    fn changes_digest_is_stable_regardless_of_map_iteration_order() {
+        let a = HashMap::from([
+            (PathBuf::from("a.txt"), add("hello")),
+            (PathBuf::from("b.txt"), add("world")),
+        ]);
+        let b = HashMap::from([
+            (PathBuf::from("b.txt"), add("world")),
+            (PathBuf::from("a.txt"), add("hello")),
+        ]);
+        assert_eq!(changes_digest(&a), changes_digest(&b));
+    }
+
+    #[test]
+    fn changes_digest_differs_for_different_content() {
+        let a = HashMap::from([(PathBuf::from("a.txt"), add("hello"))]);
+        let b = HashMap::from([(PathBuf::from("a.txt"), add("goodbye"))]);
+        assert_ne!(changes_digest(&a), changes_digest(&b));
+    }
+
+    #[test]
+    fn changes_digest_differs_for_different_paths() {
+        let a = HashMap::from([(PathBuf::from("a.txt"), add("hello"))]);
+        let b = HashMap::from([(PathBuf::from("b.txt"), add("hello"))]);
+        assert_ne!(changes_digest(&a), changes_digest(&b));
+    }
+
+    #[test]
+    fn changes_digest_differs_between_add_and_delete() {
+        let a = HashMap::from([(PathBuf::from("a.txt"), add(""))]);
+        let b = HashMap::from([(PathBuf::from("a.txt"), FileChange::Delete)]);
+        assert_ne!(changes_digest(&a), changes_digest(&b));
+    }
+
+    /// A throwaway sqlite db file under the system temp dir, removed on drop.
+    struct TempDb(PathBuf);
+
+    impl TempDb {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join("cortex-patch-audit-tests");
+            std::fs::create_dir_all(&dir).unwrap();
+            let path = dir.join(name);
+            let _ = std::fs::remove_file(&path);
+            Self(path)
+        }
+    }
+
+    impl Drop for TempDb {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    #[tokio::test]
+    async fn records_and_reads_back_a_decision_for_its_digest() {
+        let db = TempDb::new("roundtrip.sqlite3");
+        let store = PatchAuditStore::open(&db.0).await.unwrap();
+
+        store
+            .record(record("event-1", "conversation-1", "digest-1", ReviewDecision::Approved, ResolutionKind::UserAction))
+            .await;
+
+        let found = store.decisions_for_digest("conversation-1", "digest-1").await;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].event_id, "event-1");
+        assert_eq!(found[0].decision, ReviewDecision::Approved);
+
+        let remembered = store.remembered_decision("conversation-1", "digest-1").await;
+        assert_eq!(remembered, Some(ReviewDecision::Approved));
+    }
+
+    #[tokio::test]
+    async fn remembered_decision_is_scoped_to_conversation_id() {
+        let db = TempDb::new("scoping.sqlite3");
+        let store = PatchAuditStore::open(&db.0).await.unwrap();
+
+        store
+            .record(record("event-1", "conversation-a", "digest-1", ReviewDecision::Approved, ResolutionKind::UserAction))
+            .await;
+
+        assert_eq!(
+            store.remembered_decision("conversation-a", "digest-1").await,
+            Some(ReviewDecision::Approved)
+        );
+        assert_eq!(store.remembered_decision("conversation-b", "digest-1").await, None);
+    }
+
+    #[tokio::test]
+    async fn remembered_decision_ignores_timeouts_and_deserialization_failures() {
+        let db = TempDb::new("resolution-kind.sqlite3");
+        let store = PatchAuditStore::open(&db.0).await.unwrap();
+
+        store
+            .record(record("event-1", "conversation-1", "digest-1", ReviewDecision::Denied, ResolutionKind::Timeout))
+            .await;
+
+        assert_eq!(store.remembered_decision("conversation-1", "digest-1").await, None);
+        assert_eq!(store.decisions_for_digest("conversation-1", "digest-1").await.len(), 1);
+
+        store
+            .record(record("event-2", "conversation-1", "digest-2", ReviewDecision::Denied, ResolutionKind::DeserializationFailure))
+            .await;
+
+        assert_eq!(store.remembered_decision("conversation-1", "digest-2").await, None);
+    }
+}
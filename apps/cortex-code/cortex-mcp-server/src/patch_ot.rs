@@ -0,0 +1,160 @@
+use std::ops::Range;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+/// A single reviewer edit to a file's proposed new content, submitted
+/// against a specific `base_version` of that content so concurrent edits
+/// from multiple reviewers can be merged deterministically.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TextChange {
+    pub span: Range<usize>,
+    pub replacement: String,
+    pub base_version: u64,
+}
+
+/// Tracks one proposed file's content plus every `TextChange` applied to
+/// it, so an incoming change submitted against a stale `base_version` can
+/// be transformed against the operations that landed after it before being
+/// applied, the same way the codemp buffer controllers reconcile
+/// concurrent edits.
+#[derive(Debug, Clone)]
+pub struct PatchDocument {
+    content: String,
+    applied: Vec<TextChange>,
+}
+
+impl PatchDocument {
+    pub fn new(content: String) -> Self {
+        Self {
+            content,
+            applied: Vec::new(),
+        }
+    }
+
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    pub fn version(&self) -> u64 {
+        self.applied.len() as u64
+    }
+
+    /// Transforms `change` against every operation applied since its
+    /// `base_version`, then applies the result. Returns the transformed
+    /// change actually applied (to broadcast to other attached clients), or
+    /// `None` if it overlaps an operation it couldn't be reconciled
+    /// against, in which case the submitting client should re-fetch the
+    /// current content and resubmit against the latest version.
+    pub fn apply(&mut self, mut change: TextChange) -> Option<TextChange> {
+        for prior in self.applied.iter().skip(change.base_version as usize) {
+            change = transform(change, prior)?;
+        }
+
+        if change.span.start > self.content.len()
+            || change.span.end > self.content.len()
+            || change.span.start > change.span.end
+            || !self.content.is_char_boundary(change.span.start)
+            || !self.content.is_char_boundary(change.span.end)
+        {
+            return None;
+        }
+        self.content.replace_range(change.span.clone(), &change.replacement);
+
+        let applied = TextChange {
+            base_version: self.version(),
+            ..change
+        };
+        self.applied.push(applied.clone());
+        Some(applied)
+    }
+}
+
+/// Transforms `change` (submitted against an earlier version of the
+/// document) against `prior` (an operation already applied since then).
+/// Offsets entirely after `prior`'s span shift by `prior`'s net length
+/// delta; offsets entirely before are untouched. Overlapping spans can't be
+/// reconciled deterministically and are rejected.
+fn transform(change: TextChange, prior: &TextChange) -> Option<TextChange> {
+    if change.span.end <= prior.span.start {
+        return Some(change);
+    }
+
+    if change.span.start >= prior.span.end {
+        let delta = prior.replacement.len() as i64 - prior.span.len() as i64;
+        let shift = |pos: usize| -> usize { (pos as i64 + delta).max(0) as usize };
+        return Some(TextChange {
+            span: shift(change.span.start)..shift(change.span.end),
+            ..change
+        });
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(span: Range<usize>, replacement: &str, base_version: u64) -> TextChange {
+        TextChange {
+            span,
+            replacement: replacement.to_string(),
+            base_version,
+        }
+    }
+
+    #[test]
+    fn applies_a_single_change() {
+        let mut document = PatchDocument::new("hello world".to_string());
+        let applied = document.apply(change(0..5, "goodbye", 0)).unwrap();
+        assert_eq!(document.content(), "goodbye world");
+        assert_eq!(applied.base_version, 0);
+        assert_eq!(document.version(), 1);
+    }
+
+    #[test]
+    fn transforms_a_stale_change_against_a_prior_edit_after_it() {
+        let mut document = PatchDocument::new("hello world".to_string());
+        document.apply(change(0..5, "hi", 0)).unwrap();
+        // Submitted against base_version 0, before "hello" became "hi", targeting "world".
+        let applied = document.apply(change(6..11, "there", 0)).unwrap();
+        assert_eq!(document.content(), "hi there");
+        assert_eq!(applied.span, 3..8);
+    }
+
+    #[test]
+    fn transforms_a_stale_change_before_a_prior_edit_unchanged() {
+        let mut document = PatchDocument::new("hello world".to_string());
+        document.apply(change(6..11, "there", 0)).unwrap();
+        let applied = document.apply(change(0..5, "hi", 0)).unwrap();
+        assert_eq!(document.content(), "hi there");
+        assert_eq!(applied.span, 0..5);
+    }
+
+    #[test]
+    fn rejects_an_overlapping_stale_change() {
+        let mut document = PatchDocument::new("hello world".to_string());
+        document.apply(change(0..5, "goodbye", 0)).unwrap();
+        assert!(document.apply(change(3..7, "xyz", 0)).is_none());
+    }
+
+    #[test]
+    fn rejects_an_out_of_range_span() {
+        let mut document = PatchDocument::new("hi".to_string());
+        assert!(document.apply(change(0..10, "x", 0)).is_none());
+    }
+
+    #[test]
+    fn rejects_a_span_that_splits_a_multibyte_char() {
+        let mut document = PatchDocument::new("héllo".to_string());
+        // 'é' is a 2-byte UTF-8 sequence starting at byte 1; byte 2 is mid-character.
+        assert!(document.apply(change(0..2, "x", 0)).is_none());
+    }
+
+    #[test]
+    fn rejects_an_inverted_span() {
+        let mut document = PatchDocument::new("hello".to_string());
+        assert!(document.apply(change(3..1, "x", 0)).is_none());
+    }
+}
@@ -0,0 +1,186 @@
+use cortex_core::protocol::ReviewDecision;
+use serde::Serialize;
+
+/// Policy for resolving a patch approval that was fanned out to several
+/// connected MCP clients at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewerPolicy {
+    /// Apply whichever decision the first reviewer to respond returns.
+    FirstResponder,
+    /// Every registered reviewer must approve; any rejection denies the
+    /// patch immediately.
+    Unanimous,
+    /// Approve once at least `needed` of the registered reviewers approve;
+    /// deny once enough reviewers have rejected that the threshold can no
+    /// longer be reached.
+    Quorum { needed: usize },
+}
+
+/// Identity of a connected MCP client eligible to review a patch.
+pub type ReviewerId = String;
+
+/// Running tally of reviewer responses for a single patch approval,
+/// evaluated against a [`ReviewerPolicy`] after every response.
+#[derive(Debug)]
+pub struct ReviewerTally {
+    policy: ReviewerPolicy,
+    pending: Vec<ReviewerId>,
+    approved: Vec<ReviewerId>,
+    rejected: Vec<ReviewerId>,
+}
+
+/// Outcome of folding a reviewer's response into a [`ReviewerTally`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum TallyOutcome {
+    /// The policy threshold hasn't been met yet; keep waiting.
+    Pending,
+    /// The policy resolved to a final decision.
+    Resolved(ReviewDecision),
+}
+
+impl ReviewerTally {
+    pub fn new(policy: ReviewerPolicy, reviewers: Vec<ReviewerId>) -> Self {
+        Self {
+            policy,
+            pending: reviewers,
+            approved: Vec::new(),
+            rejected: Vec::new(),
+        }
+    }
+
+    /// Reviewer ids that have not yet responded, for `cortex_reviewers`.
+    pub fn pending(&self) -> &[ReviewerId] {
+        &self.pending
+    }
+
+    /// Records `reviewer`'s decision and re-evaluates the policy.
+    pub fn record(&mut self, reviewer: ReviewerId, decision: ReviewDecision) -> TallyOutcome {
+        self.pending.retain(|id| id != &reviewer);
+        match decision {
+            ReviewDecision::Approved | ReviewDecision::ApprovedForSession => {
+                self.approved.push(reviewer)
+            }
+            _ => self.rejected.push(reviewer),
+        }
+
+        match self.policy {
+            ReviewerPolicy::FirstResponder => TallyOutcome::Resolved(decision),
+            ReviewerPolicy::Unanimous => {
+                if !self.rejected.is_empty() {
+                    TallyOutcome::Resolved(ReviewDecision::Denied)
+                } else if self.pending.is_empty() {
+                    TallyOutcome::Resolved(ReviewDecision::Approved)
+                } else {
+                    TallyOutcome::Pending
+                }
+            }
+            ReviewerPolicy::Quorum { needed } => {
+                if self.approved.len() >= needed {
+                    TallyOutcome::Resolved(ReviewDecision::Approved)
+                } else if self.approved.len() + self.pending.len() < needed {
+                    // Even if every still-pending reviewer approves, the
+                    // threshold can no longer be reached.
+                    TallyOutcome::Resolved(ReviewDecision::Denied)
+                } else {
+                    TallyOutcome::Pending
+                }
+            }
+        }
+    }
+}
+
+/// A reviewer still expected to respond, as surfaced on
+/// `PatchApprovalElicitRequestParams::cortex_reviewers` so clients can
+/// render pending-reviewer state.
+#[derive(Debug, Serialize)]
+pub struct PendingReviewer {
+    pub id: ReviewerId,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reviewers(n: usize) -> Vec<ReviewerId> {
+        (0..n).map(|i| format!("reviewer-{i}")).collect()
+    }
+
+    #[test]
+    fn first_responder_resolves_on_the_first_response_regardless_of_decision() {
+        let ids = reviewers(3);
+        let mut tally = ReviewerTally::new(ReviewerPolicy::FirstResponder, ids.clone());
+        assert_eq!(
+            tally.record(ids[0].clone(), ReviewDecision::Denied),
+            TallyOutcome::Resolved(ReviewDecision::Denied)
+        );
+    }
+
+    #[test]
+    fn unanimous_stays_pending_until_everyone_approves() {
+        let ids = reviewers(3);
+        let mut tally = ReviewerTally::new(ReviewerPolicy::Unanimous, ids.clone());
+        assert_eq!(
+            tally.record(ids[0].clone(), ReviewDecision::Approved),
+            TallyOutcome::Pending
+        );
+        assert_eq!(
+            tally.record(ids[1].clone(), ReviewDecision::Approved),
+            TallyOutcome::Pending
+        );
+        assert_eq!(
+            tally.record(ids[2].clone(), ReviewDecision::Approved),
+            TallyOutcome::Resolved(ReviewDecision::Approved)
+        );
+    }
+
+    #[test]
+    fn unanimous_resolves_denied_on_the_first_rejection() {
+        let ids = reviewers(3);
+        let mut tally = ReviewerTally::new(ReviewerPolicy::Unanimous, ids.clone());
+        assert_eq!(
+            tally.record(ids[0].clone(), ReviewDecision::Approved),
+            TallyOutcome::Pending
+        );
+        assert_eq!(
+            tally.record(ids[1].clone(), ReviewDecision::Denied),
+            TallyOutcome::Resolved(ReviewDecision::Denied)
+        );
+    }
+
+    #[test]
+    fn quorum_resolves_approved_once_the_threshold_is_met() {
+        let ids = reviewers(4);
+        let mut tally = ReviewerTally::new(ReviewerPolicy::Quorum { needed: 2 }, ids.clone());
+        assert_eq!(
+            tally.record(ids[0].clone(), ReviewDecision::Approved),
+            TallyOutcome::Pending
+        );
+        assert_eq!(
+            tally.record(ids[1].clone(), ReviewDecision::Approved),
+            TallyOutcome::Resolved(ReviewDecision::Approved)
+        );
+    }
+
+    #[test]
+    fn quorum_resolves_denied_once_the_threshold_can_no_longer_be_reached() {
+        let ids = reviewers(4);
+        let mut tally = ReviewerTally::new(ReviewerPolicy::Quorum { needed: 3 }, ids.clone());
+        assert_eq!(
+            tally.record(ids[0].clone(), ReviewDecision::Denied),
+            TallyOutcome::Pending
+        );
+        // 0 approved + 2 pending < 3 needed: threshold unreachable.
+        assert_eq!(
+            tally.record(ids[1].clone(), ReviewDecision::Denied),
+            TallyOutcome::Resolved(ReviewDecision::Denied)
+        );
+    }
+
+    #[test]
+    fn quorum_keeps_pending_reviewers_accurate() {
+        let ids = reviewers(3);
+        let mut tally = ReviewerTally::new(ReviewerPolicy::Quorum { needed: 2 }, ids.clone());
+        tally.record(ids[0].clone(), ReviewDecision::Approved);
+        assert_eq!(tally.pending(), &[ids[1].clone(), ids[2].clone()]);
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::outgoing_message::OutgoingMessageSender;
+use crate::patch_ot::PatchDocument;
+use crate::patch_ot::TextChange;
+
+/// A live collaborative-editing session for one patch approval, letting
+/// reviewers edit the proposed `FileChange` contents before accepting.
+/// Correlated with the elicitation via `cortex_patch_session_id`.
+pub(crate) struct PatchSession {
+    documents: Mutex<HashMap<PathBuf, PatchDocument>>,
+    attached: Mutex<Vec<Arc<OutgoingMessageSender>>>,
+}
+
+impl PatchSession {
+    pub(crate) fn new(initial_content: HashMap<PathBuf, String>) -> Self {
+        Self {
+            documents: Mutex::new(
+                initial_content
+                    .into_iter()
+                    .map(|(path, content)| (path, PatchDocument::new(content)))
+                    .collect(),
+            ),
+            attached: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Registers `client` to receive `patchSession/textChange` broadcasts
+    /// for subsequent edits in this session.
+    pub(crate) fn attach(&self, client: Arc<OutgoingMessageSender>) {
+        self.attached.lock().unwrap().push(client);
+    }
+
+    /// Transforms and applies `change` to `path`'s document, broadcasts the
+    /// transformed change to every attached client, and returns it. Returns
+    /// `None` if `path` isn't part of this session or the change couldn't
+    /// be reconciled against edits applied since its `base_version`.
+    pub(crate) async fn apply_edit(&self, path: &PathBuf, change: TextChange) -> Option<TextChange> {
+        let applied = {
+            let mut documents = self.documents.lock().unwrap();
+            let document = documents.get_mut(path)?;
+            document.apply(change)?
+        };
+
+        let attached = self.attached.lock().unwrap().clone();
+        let notification = json!({
+            "path": path,
+            "change": applied,
+        });
+        for client in attached {
+            client
+                .send_notification("patchSession/textChange", Some(notification.clone()))
+                .await;
+        }
+
+        Some(applied)
+    }
+
+    /// Current merged content for every file in the session, to submit in
+    /// place of the agent's original proposal once approval resolves.
+    pub(crate) fn merged_content(&self) -> HashMap<PathBuf, String> {
+        self.documents
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(path, document)| (path.clone(), document.content().to_string()))
+            .collect()
+    }
+}
+
+/// Process-wide registry of in-flight patch sessions, keyed by
+/// `cortex_patch_session_id`.
+#[derive(Clone, Default)]
+pub(crate) struct PatchSessionRegistry {
+    sessions: Arc<Mutex<HashMap<String, Arc<PatchSession>>>>,
+}
+
+impl PatchSessionRegistry {
+    pub(crate) fn create(&self, session_id: String, initial_content: HashMap<PathBuf, String>) -> Arc<PatchSession> {
+        let session = Arc::new(PatchSession::new(initial_content));
+        self.sessions
+            .lock()
+            .unwrap()
+            .insert(session_id, session.clone());
+        session
+    }
+
+    pub(crate) fn get(&self, session_id: &str) -> Option<Arc<PatchSession>> {
+        self.sessions.lock().unwrap().get(session_id).cloned()
+    }
+
+    pub(crate) fn remove(&self, session_id: &str) {
+        self.sessions.lock().unwrap().remove(session_id);
+    }
+}
+
+/// Request parameters for the `patchSession/edit` method: a reviewer's MCP
+/// client submitting one `TextChange` against a file in an active patch
+/// session.
+#[derive(Debug, Deserialize)]
+pub struct PatchSessionEditParams {
+    #[serde(rename = "patchSessionId")]
+    pub patch_session_id: String,
+    pub path: PathBuf,
+    pub change: TextChange,
+}
+
+/// Handles a `patchSession/edit` request: looks up the session by
+/// `patch_session_id`, applies the submitted change, and returns the
+/// transformed change that was actually applied (broadcast to other
+/// attached clients as a side effect of [`PatchSession::apply_edit`]).
+///
+/// Returns `Err` with a message suitable for a JSON-RPC error response when
+/// the session doesn't exist, the path isn't part of it, or the change
+/// couldn't be reconciled against edits applied since its `base_version`.
+///
+/// NOTE: this is the handler body only. The server's inbound request
+/// dispatch table has to route the `patchSession/edit` method to this
+/// function for reviewer edits to actually reach a session; until that
+/// registration exists, calling this directly (as the tests below do) is
+/// the only thing that exercises it.
+pub(crate) async fn handle_patch_session_edit_request(
+    sessions: &PatchSessionRegistry,
+    params: PatchSessionEditParams,
+) -> Result<TextChange, &'static str> {
+    let session = sessions
+        .get(&params.patch_session_id)
+        .ok_or("unknown patch session")?;
+    session
+        .apply_edit(&params.path, params.change)
+        .await
+        .ok_or("edit could not be reconciled against the current session state")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(span: std::ops::Range<usize>, replacement: &str) -> TextChange {
+        TextChange {
+            span,
+            replacement: replacement.to_string(),
+            base_version: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_an_edit_for_an_unknown_session() {
+        let sessions = PatchSessionRegistry::default();
+        let params = PatchSessionEditParams {
+            patch_session_id: "missing".to_string(),
+            path: PathBuf::from("a.txt"),
+            change: change(0..1, "x"),
+        };
+        assert_eq!(
+            handle_patch_session_edit_request(&sessions, params).await.unwrap_err(),
+            "unknown patch session"
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_an_edit_for_a_path_not_in_the_session() {
+        let sessions = PatchSessionRegistry::default();
+        sessions.create(
+            "session-1".to_string(),
+            HashMap::from([(PathBuf::from("a.txt"), "hello".to_string())]),
+        );
+        let params = PatchSessionEditParams {
+            patch_session_id: "session-1".to_string(),
+            path: PathBuf::from("b.txt"),
+            change: change(0..1, "x"),
+        };
+        assert!(handle_patch_session_edit_request(&sessions, params).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn applies_an_edit_and_updates_the_session_content() {
+        let sessions = PatchSessionRegistry::default();
+        sessions.create(
+            "session-1".to_string(),
+            HashMap::from([(PathBuf::from("a.txt"), "hello world".to_string())]),
+        );
+        let params = PatchSessionEditParams {
+            patch_session_id: "session-1".to_string(),
+            path: PathBuf::from("a.txt"),
+            change: change(0..5, "goodbye"),
+        };
+        let applied = handle_patch_session_edit_request(&sessions, params).await.unwrap();
+        assert_eq!(applied.replacement, "goodbye");
+
+        let session = sessions.get("session-1").unwrap();
+        assert_eq!(session.merged_content()[&PathBuf::from("a.txt")], "goodbye world");
+    }
+}
@@ -0,0 +1,322 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use cortex_core::protocol::FileChange;
+use serde::Serialize;
+use tokio::fs;
+use tokio::process::Command;
+use tokio::time::timeout;
+use tracing::error;
+use tracing::warn;
+
+/// Directory (relative to the grant root) under which ephemeral
+/// verification worktrees are created and cleaned up.
+const WORKTREE_DIR: &str = ".cortex-patch-verify";
+
+/// Directory (relative to the grant root) where verification run artifacts
+/// are retained as `<event_id>.log`: the command, exit status, timeout flag
+/// and the same trailing `LOG_TAIL_BYTES` of stdout/stderr as `log_tail`.
+/// Output before that tail is not kept.
+const ARTIFACTS_DIR: &str = ".cortex-patch-verify/artifacts";
+
+/// How long we're willing to wait for the configured verification command
+/// before giving up and reporting the run as `timed_out`, on which the
+/// approval handler denies the patch.
+const VERIFICATION_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Number of trailing bytes of combined stdout/stderr to keep inline in the
+/// elicitation payload.
+const LOG_TAIL_BYTES: usize = 4096;
+
+/// Outcome of running the configured verification command against a
+/// materialized copy of the proposed changes. Included on
+/// `PatchApprovalElicitRequestParams` so the reviewer can see evidence the
+/// patch builds before approving it.
+#[derive(Debug, Clone, Serialize)]
+pub struct PatchVerification {
+    pub command: String,
+    pub passed: bool,
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    /// Trailing slice of combined stdout/stderr, for inline display.
+    pub log_tail: String,
+    /// Path of the persisted log file. It holds the command, exit code,
+    /// timeout flag and the same trailing slice as `log_tail`, not the full
+    /// output.
+    pub artifacts_path: Option<PathBuf>,
+}
+
+/// Materializes `changes` on top of a `git worktree` checked out from
+/// `grant_root`, runs `command` inside it, and persists the result to
+/// `ARTIFACTS_DIR/<event_id>.log`. Returns `None` when no verification command
+/// is configured, meaning the caller should proceed without evidence.
+///
+/// Verification requires `grant_root` to point at the repository being
+/// patched, since that's what lets the worktree start from a real,
+/// buildable checkout rather than just the handful of paths the patch
+/// touches; without it there's nothing trustworthy to run `command`
+/// against, so we report that rather than running it against an empty
+/// directory and treating a guaranteed failure as evidence of anything.
+pub(crate) async fn verify_patch(
+    event_id: &str,
+    grant_root: Option<&Path>,
+    changes: &HashMap<PathBuf, FileChange>,
+    command: &[String],
+) -> Option<PatchVerification> {
+    if command.is_empty() {
+        return None;
+    }
+    let command_line = command.join(" ");
+
+    let Some(grant_root) = grant_root else {
+        return Some(PatchVerification {
+            command: command_line,
+            passed: false,
+            exit_code: None,
+            timed_out: false,
+            log_tail: "no grant root available to seed a verification worktree from the real repo; skipping verification"
+                .to_string(),
+            artifacts_path: None,
+        });
+    };
+    let worktree = grant_root.join(WORKTREE_DIR).join(event_id);
+
+    let outcome = match materialize_and_run(grant_root, &worktree, changes, command).await {
+        Ok(outcome) => outcome,
+        Err(err) => {
+            error!("failed to verify patch for event {event_id}: {err}");
+            PatchVerification {
+                command: command_line.clone(),
+                passed: false,
+                exit_code: None,
+                timed_out: false,
+                log_tail: format!("verification failed before the command could run: {err}"),
+                artifacts_path: None,
+            }
+        }
+    };
+
+    if let Err(err) = remove_worktree(grant_root, &worktree).await {
+        warn!("failed to clean up verification worktree {worktree:?}: {err}");
+    }
+
+    Some(persist_artifacts(grant_root, event_id, outcome).await)
+}
+
+async fn materialize_and_run(
+    base_repo: &Path,
+    worktree: &Path,
+    changes: &HashMap<PathBuf, FileChange>,
+    command: &[String],
+) -> anyhow::Result<PatchVerification> {
+    seed_worktree_from_repo(base_repo, worktree).await?;
+
+    for (path, change) in changes {
+        apply_change_to_worktree(worktree, path, change).await?;
+    }
+
+    let command_line = command.join(" ");
+    let run = timeout(VERIFICATION_TIMEOUT, run_command(command, worktree));
+    Ok(match run.await {
+        Ok(Ok((exit_code, log))) => PatchVerification {
+            command: command_line,
+            passed: exit_code == Some(0),
+            exit_code,
+            timed_out: false,
+            log_tail: tail(&log, LOG_TAIL_BYTES),
+            artifacts_path: None,
+        },
+        Ok(Err(err)) => PatchVerification {
+            command: command_line,
+            passed: false,
+            exit_code: None,
+            timed_out: false,
+            log_tail: format!("failed to run verification command: {err}"),
+            artifacts_path: None,
+        },
+        Err(_) => PatchVerification {
+            command: command_line,
+            passed: false,
+            exit_code: None,
+            timed_out: true,
+            log_tail: "verification command timed out".to_string(),
+            artifacts_path: None,
+        },
+    })
+}
+
+/// Checks out a `git worktree` at `worktree` from `base_repo`'s current
+/// `HEAD`, so verification runs against the real tree rather than just the
+/// paths touched by the proposed changes. `--force` reuses a leftover
+/// worktree directory from a prior run that failed to clean up.
+async fn seed_worktree_from_repo(base_repo: &Path, worktree: &Path) -> anyhow::Result<()> {
+    if let Some(parent) = worktree.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    let output = Command::new("git")
+        .args(["worktree", "add", "--detach", "--force"])
+        .arg(worktree)
+        .arg("HEAD")
+        .current_dir(base_repo)
+        .output()
+        .await?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git worktree add failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(())
+}
+
+/// Removes a worktree created by [`seed_worktree_from_repo`], via
+/// `git worktree remove` rather than a plain directory delete so git's own
+/// worktree bookkeeping in `base_repo` stays consistent.
+async fn remove_worktree(base_repo: &Path, worktree: &Path) -> anyhow::Result<()> {
+    let output = Command::new("git")
+        .args(["worktree", "remove", "--force"])
+        .arg(worktree)
+        .current_dir(base_repo)
+        .output()
+        .await?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git worktree remove failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(())
+}
+
+async fn apply_change_to_worktree(
+    worktree: &Path,
+    path: &Path,
+    change: &FileChange,
+) -> anyhow::Result<()> {
+    match change {
+        FileChange::Add { content } => {
+            let dest = worktree.join(path);
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            fs::write(dest, content).await?;
+        }
+        FileChange::Delete => {
+            let dest = worktree.join(path);
+            if fs::try_exists(&dest).await.unwrap_or(false) {
+                fs::remove_file(dest).await?;
+            }
+        }
+        FileChange::Update {
+            new_content,
+            move_path,
+            ..
+        } => {
+            let dest = worktree.join(move_path.as_deref().unwrap_or(path));
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            fs::write(dest, new_content).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Runs `command` with `cwd` as the working directory, returning its exit
+/// code and combined stdout/stderr.
+async fn run_command(command: &[String], cwd: &Path) -> anyhow::Result<(Option<i32>, String)> {
+    let (program, args) = command
+        .split_first()
+        .ok_or_else(|| anyhow::anyhow!("empty verification command"))?;
+
+    let output = Command::new(program)
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .await?;
+
+    let mut log = String::from_utf8_lossy(&output.stdout).into_owned();
+    log.push_str(&String::from_utf8_lossy(&output.stderr));
+    Ok((output.status.code(), log))
+}
+
+fn tail(log: &str, max_bytes: usize) -> String {
+    if log.len() <= max_bytes {
+        return log.to_string();
+    }
+    let start = log.len() - max_bytes;
+    // Avoid splitting in the middle of a UTF-8 code point.
+    let boundary = (start..log.len())
+        .find(|&idx| log.is_char_boundary(idx))
+        .unwrap_or(start);
+    log[boundary..].to_string()
+}
+
+async fn persist_artifacts(
+    base: &Path,
+    event_id: &str,
+    mut outcome: PatchVerification,
+) -> PatchVerification {
+    let artifacts_dir = base.join(ARTIFACTS_DIR);
+    if let Err(err) = fs::create_dir_all(&artifacts_dir).await {
+        warn!("failed to create verification artifacts dir: {err}");
+        return outcome;
+    }
+
+    let log_path = artifacts_dir.join(format!("{event_id}.log"));
+    let contents = format!(
+        "command: {}\nexit_code: {:?}\ntimed_out: {}\n\n{}",
+        outcome.command, outcome.exit_code, outcome.timed_out, outcome.log_tail
+    );
+    match fs::write(&log_path, contents).await {
+        Ok(()) => outcome.artifacts_path = Some(log_path),
+        Err(err) => warn!("failed to persist verification log for {event_id}: {err}"),
+    }
+
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tail_returns_the_whole_log_when_its_within_the_limit() {
+        assert_eq!(tail("hello", 10), "hello");
+    }
+
+    #[test]
+    fn tail_truncates_to_the_trailing_bytes() {
+        assert_eq!(tail("0123456789", 4), "6789");
+    }
+
+    #[test]
+    fn tail_does_not_split_a_multibyte_char_at_the_boundary() {
+        // "héllo world" is 12 bytes ('é' is a 2-byte sequence at bytes 1..3);
+        // a 10-byte tail would naively cut at byte 2, mid-character, so the
+        // boundary search has to shift forward to byte 3 instead.
+        let log = "héllo world";
+        let truncated = tail(log, 10);
+        assert_eq!(truncated, "llo world");
+    }
+
+    #[tokio::test]
+    async fn verify_patch_without_a_grant_root_skips_verification() {
+        let result = verify_patch("event-1", None, &HashMap::new(), &["echo".to_string(), "hi".to_string()])
+            .await
+            .unwrap();
+        assert!(!result.passed);
+        assert!(!result.timed_out);
+        assert_eq!(result.exit_code, None);
+        assert!(result.artifacts_path.is_none());
+        assert!(result.log_tail.contains("no grant root"));
+    }
+
+    #[tokio::test]
+    async fn verify_patch_with_no_command_configured_returns_none() {
+        let root = std::env::temp_dir();
+        assert!(verify_patch("event-1", Some(&root), &HashMap::new(), &[]).await.is_none());
+    }
+}
@@ -10,17 +10,23 @@ use tokio::sync::mpsc::unbounded_channel;
 
 use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
+use crate::chatwidget::event_log::EventLog;
+use crate::chatwidget::event_log::NullEventLogStore;
 
 /// Spawn the agent bootstrapper and op forwarding loop, returning the
-/// `UnboundedSender<Op>` used by the UI to submit operations.
+/// `UnboundedSender<Op>` used by the UI to submit operations and the
+/// `EventLog` backing it, so a later reconnect can be replayed via
+/// [`spawn_agent_from_existing`].
 pub(crate) fn spawn_agent(
     config: Config,
     app_event_tx: AppEventSender,
     server: Arc<ConversationManager>,
-) -> UnboundedSender<Op> {
+) -> (UnboundedSender<Op>, EventLog) {
     let (cortex_op_tx, mut cortex_op_rx) = unbounded_channel::<Op>();
+    let event_log = EventLog::new(Arc::new(NullEventLogStore));
 
     let app_event_tx_clone = app_event_tx.clone();
+    let event_log_clone = event_log.clone();
     tokio::spawn(async move {
         let NewConversation {
             conversation_id: _,
@@ -41,7 +47,8 @@ pub(crate) fn spawn_agent(
             id: "".to_string(),
             msg: cortex_core::protocol::EventMsg::SessionConfigured(session_configured),
         };
-        app_event_tx_clone.send(AppEvent::CortexEvent(ev));
+        let sequenced = event_log_clone.append(ev);
+        app_event_tx_clone.send(AppEvent::CortexEvent(sequenced.event));
 
         let conversation_clone = conversation.clone();
         tokio::spawn(async move {
@@ -54,31 +61,47 @@ pub(crate) fn spawn_agent(
         });
 
         while let Ok(event) = conversation.next_event().await {
-            app_event_tx_clone.send(AppEvent::CortexEvent(event));
+            let sequenced = event_log_clone.append(event);
+            app_event_tx_clone.send(AppEvent::CortexEvent(sequenced.event));
         }
     });
 
-    cortex_op_tx
+    (cortex_op_tx, event_log)
 }
 
-/// Spawn agent loops for an existing conversation (e.g., a forked conversation).
-/// Sends the provided `SessionConfiguredEvent` immediately, then forwards subsequent
-/// events and accepts Ops for submission.
+/// Spawn agent loops for an existing conversation (e.g., a forked conversation
+/// or a client reattaching after a reconnect).
+///
+/// If `resume_from_seq` is `Some`, the backlog of events recorded in
+/// `event_log` since that sequence is replayed to `app_event_tx` before the
+/// provided `SessionConfiguredEvent` and subsequent live events, so a
+/// reattached UI doesn't lose events that arrived while its channel was
+/// detached.
 pub(crate) fn spawn_agent_from_existing(
     conversation: std::sync::Arc<CortexConversation>,
     session_configured: cortex_core::protocol::SessionConfiguredEvent,
     app_event_tx: AppEventSender,
+    event_log: EventLog,
+    resume_from_seq: Option<u64>,
 ) -> UnboundedSender<Op> {
     let (cortex_op_tx, mut cortex_op_rx) = unbounded_channel::<Op>();
 
+    if let Some(seq) = resume_from_seq {
+        for sequenced in event_log.resume_from(seq) {
+            app_event_tx.send(AppEvent::CortexEvent(sequenced.event));
+        }
+    }
+
     let app_event_tx_clone = app_event_tx.clone();
+    let event_log_clone = event_log.clone();
     tokio::spawn(async move {
         // Forward the captured `SessionConfigured` event so it can be rendered in the UI.
         let ev = cortex_core::protocol::Event {
             id: "".to_string(),
             msg: cortex_core::protocol::EventMsg::SessionConfigured(session_configured),
         };
-        app_event_tx_clone.send(AppEvent::CortexEvent(ev));
+        let sequenced = event_log_clone.append(ev);
+        app_event_tx_clone.send(AppEvent::CortexEvent(sequenced.event));
 
         let conversation_clone = conversation.clone();
         tokio::spawn(async move {
@@ -91,7 +114,8 @@ pub(crate) fn spawn_agent_from_existing(
         });
 
         while let Ok(event) = conversation.next_event().await {
-            app_event_tx_clone.send(AppEvent::CortexEvent(event));
+            let sequenced = event_log_clone.append(event);
+            app_event_tx_clone.send(AppEvent::CortexEvent(sequenced.event));
         }
     });
 
@@ -0,0 +1,205 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use cortex_core::protocol::Event as CortexEvent;
+
+/// Number of events retained in the in-memory ring buffer for replay after
+/// a reconnect. Events older than this are only available if a durable
+/// `EventLogStore` is configured.
+const RING_BUFFER_CAPACITY: usize = 1024;
+
+/// A `CortexEvent` tagged with the monotonically increasing sequence
+/// number it was assigned on append, so a reattaching `AppEventSender` can
+/// ask to resume from the last sequence it acknowledged.
+#[derive(Debug, Clone)]
+pub(crate) struct SequencedEvent {
+    pub seq: u64,
+    pub event: CortexEvent,
+}
+
+/// Durable backing store for events the in-memory ring buffer has already
+/// evicted. The default implementation is a no-op; a real deployment would
+/// back this with sqlite via a pooled `bb8` connection, the same pooling
+/// pattern the patch-approval audit trail uses.
+pub(crate) trait EventLogStore: Send + Sync {
+    fn append(&self, event: &SequencedEvent);
+    fn events_since(&self, seq: u64) -> Vec<SequencedEvent>;
+}
+
+/// No-op store used when no durable backing is configured.
+pub(crate) struct NullEventLogStore;
+
+impl EventLogStore for NullEventLogStore {
+    fn append(&self, _event: &SequencedEvent) {}
+
+    fn events_since(&self, _seq: u64) -> Vec<SequencedEvent> {
+        Vec::new()
+    }
+}
+
+/// Pure sequence-numbering and bounded-eviction logic behind `EventLog`,
+/// generic over the retained payload so it can be exercised directly in
+/// tests: `CortexEvent` has no public constructor here (its only variant in
+/// use, `EventMsg::SessionConfigured`, wraps a `cortex_core` type this crate
+/// never builds itself, only forwards), so tests cover this instead of
+/// `EventLog` directly.
+struct SequencedRing<T> {
+    next_seq: u64,
+    capacity: usize,
+    ring: VecDeque<(u64, T)>,
+}
+
+impl<T: Clone> SequencedRing<T> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            next_seq: 0,
+            capacity,
+            ring: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Assigns the next sequence number to `value`, evicting the oldest
+    /// retained entry first if the ring is already at capacity.
+    fn push(&mut self, value: T) -> (u64, T) {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        if self.ring.len() == self.capacity {
+            self.ring.pop_front();
+        }
+        self.ring.push_back((seq, value.clone()));
+        (seq, value)
+    }
+
+    /// Returns every ring-retained entry with a sequence number greater than
+    /// `seq`, plus whether the ring held the full backlog since `seq` (i.e.
+    /// its oldest entry is still `seq + 1` or earlier) so the caller knows
+    /// whether to fall back to a durable store for anything evicted.
+    fn since(&self, seq: u64) -> (bool, Vec<(u64, T)>) {
+        let has_full_backlog = self.ring.front().is_none_or(|(oldest, _)| *oldest <= seq + 1);
+        let entries = self.ring.iter().filter(|(s, _)| *s > seq).cloned().collect();
+        (has_full_backlog, entries)
+    }
+}
+
+struct Inner {
+    ring: SequencedRing<CortexEvent>,
+}
+
+/// Assigns sequence numbers to every `CortexEvent` a conversation emits and
+/// retains a bounded ring buffer of recent events, so a freshly (re)attached
+/// `AppEventSender` can replay the backlog since its last acknowledged
+/// sequence before live events resume. Cheap to clone; the underlying
+/// state is shared.
+#[derive(Clone)]
+pub(crate) struct EventLog {
+    inner: Arc<Mutex<Inner>>,
+    store: Arc<dyn EventLogStore>,
+}
+
+impl EventLog {
+    pub(crate) fn new(store: Arc<dyn EventLogStore>) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                ring: SequencedRing::new(RING_BUFFER_CAPACITY),
+            })),
+            store,
+        }
+    }
+
+    /// Assigns the next sequence number to `event`, retains it, and returns
+    /// the sequenced event to forward to live listeners.
+    pub(crate) fn append(&self, event: CortexEvent) -> SequencedEvent {
+        let (seq, event) = self.inner.lock().unwrap().ring.push(event);
+        let sequenced = SequencedEvent { seq, event };
+        self.store.append(&sequenced);
+        sequenced
+    }
+
+    /// Returns every retained event with a sequence number greater than
+    /// `seq`, falling back to the durable store for anything the ring
+    /// buffer has already evicted.
+    pub(crate) fn resume_from(&self, seq: u64) -> Vec<SequencedEvent> {
+        let (has_full_backlog, entries) = self.inner.lock().unwrap().ring.since(seq);
+        let oldest_retained = entries.first().map_or(u64::MAX, |(seq, _)| *seq);
+        let retained = entries
+            .into_iter()
+            .map(|(seq, event)| SequencedEvent { seq, event });
+        if has_full_backlog {
+            return retained.collect();
+        }
+        let mut backlog: Vec<SequencedEvent> = self
+            .store
+            .events_since(seq)
+            .into_iter()
+            .filter(|sequenced| sequenced.seq < oldest_retained)
+            .collect();
+        backlog.extend(retained);
+        backlog
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_assigns_increasing_sequence_numbers() {
+        let mut ring = SequencedRing::new(4);
+        assert_eq!(ring.push("a").0, 0);
+        assert_eq!(ring.push("b").0, 1);
+        assert_eq!(ring.push("c").0, 2);
+    }
+
+    #[test]
+    fn push_evicts_the_oldest_entry_once_over_capacity() {
+        let mut ring = SequencedRing::new(2);
+        ring.push("a");
+        ring.push("b");
+        ring.push("c");
+        let (_, entries) = ring.since(0);
+        assert_eq!(entries, vec![(1, "b"), (2, "c")]);
+    }
+
+    #[test]
+    fn since_returns_only_entries_after_the_given_sequence() {
+        let mut ring = SequencedRing::new(4);
+        ring.push("a");
+        ring.push("b");
+        ring.push("c");
+        let (_, entries) = ring.since(1);
+        assert_eq!(entries, vec![(2, "c")]);
+    }
+
+    #[test]
+    fn since_reports_a_full_backlog_when_nothing_has_been_evicted() {
+        let mut ring = SequencedRing::new(4);
+        ring.push("a");
+        ring.push("b");
+        let (has_full_backlog, _) = ring.since(0);
+        assert!(has_full_backlog);
+    }
+
+    #[test]
+    fn since_reports_an_incomplete_backlog_once_the_requested_range_was_evicted() {
+        let mut ring = SequencedRing::new(2);
+        ring.push("a");
+        ring.push("b");
+        ring.push("c");
+        ring.push("d");
+        // Resuming after seq 0 needs seq 1 ("b"), which was evicted along
+        // with "a", so the ring can no longer answer for it.
+        let (has_full_backlog, _) = ring.since(0);
+        assert!(!has_full_backlog);
+    }
+
+    #[test]
+    fn since_at_the_latest_sequence_returns_nothing() {
+        let mut ring = SequencedRing::new(4);
+        ring.push("a");
+        ring.push("b");
+        let (has_full_backlog, entries) = ring.since(1);
+        assert!(has_full_backlog);
+        assert!(entries.is_empty());
+    }
+}